@@ -57,7 +57,6 @@ extern crate winapi;
 
 use std::io;
 use std::path::Path;
-use std::os::unix::fs::PermissionsExt;
 /// Returns `true` if there is a file at the given path and it is
 /// executable. Returns `false` otherwise.
 ///
@@ -82,43 +81,222 @@ pub trait IsExecutable {
     fn is_executable(&self) -> bool;
 }
 
-/// Returns `Result<Path, io::Error>` if there is a file at the given path and the
-/// current run-level is permitted to execute it.
+/// Returns `true` if there is a file at the given path, it is executable,
+/// and the *current* process would actually be allowed to execute it.
 ///
-/// See the module documentation for details.
-pub fn is_permitted<P>(path: P) -> Result<::std::path::PathBuf, io::Error>
+/// Unlike [`is_executable`], which only inspects the file's permission
+/// bits, this also takes into account who owns the file, what groups the
+/// calling process belongs to, and platform-specific access controls such
+/// as ACLs or a `noexec` mount, by delegating to the same check the
+/// operating system performs when you try to run the file yourself.
+///
+/// This applies on unix only. On Windows, WASI and bare wasm this is the
+/// same check as [`is_executable`].
+pub fn is_executable_by_current_user<P>(path: P) -> bool
 where
-    P: AsRef<Path>
+    P: AsRef<Path>,
 {
-    path.as_ref().is_permitted()
+    path.as_ref().is_executable_by_current_user()
 }
 
-/// An extension trait for `std::fs::Path` providing an `is_permitted` method.
+/// An extension trait for `std::fs::Path` providing an
+/// `is_executable_by_current_user` method.
 ///
 /// See the module documentation for examples.
-pub trait IsPermitted {
-    /// Returns `Result<Path, io::Error>` that describes if a particular file
-    /// exists at the given path and the run-level of the current context meets
-    /// the appropriate user, group, admin, root/system-level membership.
+pub trait IsExecutableByCurrentUser {
+    /// Returns `true` if there is a file at the given path, it is
+    /// executable, and the current process would actually be allowed to
+    /// execute it.
+    ///
+    /// See [`is_executable_by_current_user`] for details.
+    fn is_executable_by_current_user(&self) -> bool;
+}
+
+/// Search the directories listed in the `PATH` environment variable for an
+/// executable file named `name`, returning the first match.
+///
+/// Directories are searched in the order they appear in `PATH`. On Windows,
+/// if `name` has no extension, each `PATHEXT` suffix (`.exe`, `.bat`, ...) is
+/// tried in turn, mirroring how `cmd.exe` resolves bare command names.
+///
+/// Returns `None` if `PATH` is not set, or if no executable named `name` is
+/// found in any of its directories.
+pub fn find_in_path<S>(name: S) -> Option<::std::path::PathBuf>
+where
+    S: AsRef<::std::ffi::OsStr>,
+{
+    find_all_in_path(name).into_iter().next()
+}
+
+/// Like [`find_in_path`], but returns every matching executable found in
+/// `PATH`, in search order, rather than stopping at the first.
+pub fn find_all_in_path<S>(name: S) -> Vec<::std::path::PathBuf>
+where
+    S: AsRef<::std::ffi::OsStr>,
+{
+    let name = name.as_ref();
+
+    let path = match ::std::env::var_os("PATH") {
+        Some(path) => path,
+        None => return Vec::new(),
+    };
+
+    ::std::env::split_paths(&path)
+        .flat_map(|dir| path_candidates(&dir, name))
+        .filter(|candidate| candidate.is_executable())
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn path_candidates(dir: &Path, name: &::std::ffi::OsStr) -> Vec<::std::path::PathBuf> {
+    vec![dir.join(name)]
+}
+
+#[cfg(target_os = "windows")]
+fn path_candidates(dir: &Path, name: &::std::ffi::OsStr) -> Vec<::std::path::PathBuf> {
+    if Path::new(name).extension().is_some() {
+        return vec![dir.join(name)];
+    }
+    windows::pathext_candidates(dir, name)
+}
+
+#[cfg(unix)]
+use unix::is_executable_no_follow;
+#[cfg(target_os = "windows")]
+use windows::is_executable_no_follow;
+#[cfg(target_os = "wasi")]
+use wasi::is_executable_no_follow;
+#[cfg(all(target_family = "wasm", not(target_os = "wasi")))]
+use wasm::is_executable_no_follow;
+
+/// A configurable executable check, for callers who need more control than
+/// the default [`is_executable`] function and [`IsExecutable`] trait offer.
+///
+/// By default an `ExecutableCheck` behaves exactly like [`is_executable`]:
+/// it follows symlinks and only consults the file's permission bits. Use
+/// [`follow_symlinks`](ExecutableCheck::follow_symlinks) to check the
+/// path without following a symlink (a symlink itself then never counts
+/// as executable), and
+/// [`require_current_user`](ExecutableCheck::require_current_user) to
+/// additionally require that the *current* process could actually execute
+/// the file, as with [`is_executable_by_current_user`].
+///
+/// ```rust
+/// use is_executable::ExecutableCheck;
+///
+/// let is_executable = ExecutableCheck::new()
+///     .follow_symlinks(false)
+///     .require_current_user(true)
+///     .is_executable("some/path/to/a/file");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ExecutableCheck {
+    follow_symlinks: bool,
+    require_current_user: bool,
+}
+
+impl Default for ExecutableCheck {
+    fn default() -> ExecutableCheck {
+        ExecutableCheck {
+            follow_symlinks: true,
+            require_current_user: false,
+        }
+    }
+}
+
+impl ExecutableCheck {
+    /// Construct a new `ExecutableCheck` with the default options: follow
+    /// symlinks, and don't require current-user access.
+    pub fn new() -> ExecutableCheck {
+        ExecutableCheck::default()
+    }
+
+    /// If set to `false`, don't follow a symlink at `path`: the path's own
+    /// metadata is checked, and since a symlink is not a regular file it is
+    /// reported as not executable, whatever it points to. On Windows this
+    /// option has no effect. Defaults to `true`.
+    pub fn follow_symlinks(&mut self, follow_symlinks: bool) -> &mut ExecutableCheck {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// If set to `true`, also require that the current process could
+    /// actually execute the file, per [`is_executable_by_current_user`].
+    /// Defaults to `false`.
     ///
-    /// Note: *this does not inspect whether the `Path` is executable.*
-    fn is_permitted(&self) -> Result<::std::path::PathBuf, ::std::io::Error>;
+    /// On platforms without a unix-style owner/group/other permission model
+    /// (Windows, WASI, bare wasm), [`is_executable_by_current_user`] just
+    /// falls back to the same check as [`is_executable`], so this option is
+    /// a no-op there.
+    pub fn require_current_user(&mut self, require_current_user: bool) -> &mut ExecutableCheck {
+        self.require_current_user = require_current_user;
+        self
+    }
+
+    /// Run this check against `path`.
+    pub fn is_executable<P>(&self, path: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        let is_executable = if self.follow_symlinks {
+            path.is_executable()
+        } else {
+            is_executable_no_follow(path)
+        };
+
+        is_executable && (!self.require_current_user || path.is_executable_by_current_user())
+    }
+}
+
+/// Returns an iterator over every executable file directly inside `dir`.
+///
+/// Entries that cannot be read, such as broken symlinks, are silently
+/// skipped rather than returned as an error.
+pub fn executables_in_dir<P>(dir: P) -> io::Result<impl Iterator<Item = ::std::path::PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    let entries = ::std::fs::read_dir(dir)?;
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_executable()))
+}
+
+/// Returns an iterator over every executable reachable via the `PATH`
+/// environment variable, deduplicated by file name so that a name shadowed
+/// by an earlier `PATH` entry is only yielded once, just as a shell would
+/// resolve it.
+///
+/// Directories in `PATH` that cannot be read (for example, due to
+/// permissions) are skipped instead of aborting the whole scan.
+pub fn path_executables() -> impl Iterator<Item = ::std::path::PathBuf> {
+    let path = ::std::env::var_os("PATH").unwrap_or_default();
+    let dirs = ::std::env::split_paths(&path).collect::<Vec<_>>();
+    let mut seen = ::std::collections::HashSet::new();
+
+    dirs.into_iter()
+        .filter_map(|dir| executables_in_dir(dir).ok())
+        .flatten()
+        .filter(move |path| match path.file_name() {
+            Some(name) => seen.insert(name.to_os_string()),
+            None => false,
+        })
 }
 
 #[cfg(unix)]
 mod unix {
     use Path;
-    use std::os::unix::fs::MetadataExt;
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
     use std::os::unix::fs::PermissionsExt;
 
-    extern crate users;
-    use self::users::{
-        group_access_list,
-        get_effective_uid,
-    }; 
-    use std::fs; 
+    extern crate libc;
+
     use super::IsExecutable;
-    use super::IsPermitted;
+    use super::IsExecutableByCurrentUser;
 
     impl IsExecutable for Path {
         fn is_executable(&self) -> bool {
@@ -131,74 +309,107 @@ mod unix {
         }
     }
 
-    /// Could this target path be ran with executable permissions
-    ///  by this runtime's run-level user?
-    ///
-    ///  Suppose the GID for the file in question is (Gm).
-    ///  Assuming that the set of all groups shared by the user, 
-    ///  (G* := { Gn, Gn+1, Gn+2, ...}), is a superset of the set whose only
-    ///  entry is the user's GID, (G_user := G* - Gn).
-    ///  Then, Ǝ a possibility some arbitary GID, like (Gn+14) for example,
-    ///  happens to be the GID belonging to group on that file.
-    ///  
-    ///  In other words, we'll collect each of the (G*) entries
-    ///  and see if there's a match. Otherwise, we check who owns the file and
-    ///  perform a similar check.
-    impl IsPermitted for Path {
-        fn is_permitted(&self) -> Result<::std::path::PathBuf, ::std::io::Error> {
-            let (metadata, buf)  = match self.metadata() {
-                Ok(md) => { (Some(md), self.to_path_buf()) },
-                Err(e) => { return Err(e) }
+    /// Like `IsExecutable::is_executable`, but without following a symlink:
+    /// a symlink is not a regular file, so it is always reported as not
+    /// executable.
+    pub(crate) fn is_executable_no_follow(path: &Path) -> bool {
+        let metadata = match path.symlink_metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => return false,
+        };
+        let permissions = metadata.permissions();
+        metadata.is_file() && permissions.mode() & 0o111 != 0
+    }
+
+    impl IsExecutableByCurrentUser for Path {
+        fn is_executable_by_current_user(&self) -> bool {
+            // `faccessat` alone would report `true` for directories, since
+            // they carry a search/exec bit of their own; guard on regular
+            // files first, same as `is_executable` does.
+            match self.metadata() {
+                Ok(metadata) if metadata.is_file() => {}
+                _ => return false,
+            }
+
+            // Delegate to `faccessat(2)` with `AT_EACCESS`: it already
+            // implements the kernel's effective-credential algorithm
+            // (compare the caller's euid against the file's owner, else its
+            // egid and supplementary groups against the file's group, else
+            // fall back to the "other" bits, with root permitted to execute
+            // anything with at least one executable bit set), and it also
+            // accounts for things a manual stat-and-compare can't see, like
+            // ACLs or a `noexec` mount. Plain `access(2)` checks the *real*
+            // uid/gid instead, which is wrong for setuid/setgid processes,
+            // so `AT_EACCESS` is required here.
+            let path = match CString::new(self.as_os_str().as_bytes()) {
+                Ok(path) => path,
+                Err(_) => return false,
             };
-            match metadata.unwrap().is_file() {
-                true => { 
-                    let file_gid: u32 = fs::metadata(buf.to_str().unwrap()).unwrap().gid();
-                    if let Some(_gid_match) = group_access_list()
-                                             .unwrap()
-                                             .into_iter()
-                                             .take_while(|grp| file_gid != grp.gid())
-                                             .last() { return Ok(buf) }
-                    else if fs::metadata(self.to_str().unwrap())
-                                             .unwrap().uid() == get_effective_uid() {
-                        Ok(buf)
-                    }
-                    else {
-                        Err(::std::io::Error::new(::std::io::ErrorKind::PermissionDenied, "Access denied."))
-                    }
-                }
-                false => { Err(::std::io::Error::new(::std::io::ErrorKind::NotFound, "Path not found")) }, 
-           }
-        } 
+            unsafe {
+                libc::faccessat(
+                    libc::AT_FDCWD,
+                    path.as_ptr(),
+                    libc::X_OK,
+                    libc::AT_EACCESS,
+                ) == 0
+            }
+        }
     }
 }
 
 #[cfg(target_os = "windows")]
 mod windows {
+    use std::ffi::OsStr;
     use std::os::windows::ffi::OsStrExt;
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
 
     use winapi::ctypes::{c_ulong, wchar_t};
     use winapi::um::winbase::GetBinaryTypeW;
 
     use super::IsExecutable;
+    use super::IsExecutableByCurrentUser;
+
+    /// Parse the `PATHEXT` environment variable into the list of extensions
+    /// (without the leading `.`) that Windows considers executable.
+    ///
+    /// https://github.com/nushell/nushell/blob/93e8f6c05e1e1187d5b674d6b633deb839c84899/crates/nu-cli/src/completion/command.rs#L64-L74
+    pub(crate) fn pathext() -> Vec<String> {
+        let pathext = match std::env::var_os("PATHEXT") {
+            Some(pathext) => pathext,
+            None => return Vec::new(),
+        };
+        pathext
+            .to_string_lossy()
+            .split(';')
+            // Filter out empty tokens and ';' at the end
+            .filter(|f| f.len() > 1)
+            // Cut off the leading '.' character
+            .map(|ext| ext[1..].to_string())
+            .collect()
+    }
+
+    /// The candidate file names to try in `dir` for a PATH-style lookup of
+    /// `name` that has no extension of its own: `name` suffixed with each
+    /// `PATHEXT` extension in turn.
+    pub(crate) fn pathext_candidates(dir: &Path, name: &OsStr) -> Vec<PathBuf> {
+        pathext()
+            .into_iter()
+            .map(|ext| {
+                let mut file_name = name.to_os_string();
+                file_name.push(".");
+                file_name.push(ext);
+                dir.join(file_name)
+            })
+            .collect()
+    }
 
     impl IsExecutable for Path {
         fn is_executable(&self) -> bool {
             // Check using file extension
-            if let Some(pathext) = std::env::var_os("PATHEXT") {
-                if let Some(extension) = self.extension() {
-                    // Restructure pathext as Vec<String>
-                    // https://github.com/nushell/nushell/blob/93e8f6c05e1e1187d5b674d6b633deb839c84899/crates/nu-cli/src/completion/command.rs#L64-L74
-                    let pathext = pathext
-                        .to_string_lossy()
-                        .split(';')
-                        // Filter out empty tokens and ';' at the end
-                        .filter(|f| f.len() > 1)
-                        // Cut off the leading '.' character
-                        .map(|ext| ext[1..].to_string())
-                        .collect::<Vec<_>>();
+            if let Some(extension) = self.extension() {
+                let pathext = pathext();
+                if !pathext.is_empty() {
                     let extension = extension.to_string_lossy();
-
                     return pathext
                         .iter()
                         .any(|ext| extension.eq_ignore_ascii_case(ext));
@@ -239,4 +450,89 @@ mod windows {
             false
         }
     }
+
+    /// Windows doesn't expose a cheap way to tell a symlink apart from its
+    /// target when classifying binaries, so this just falls back to
+    /// following the symlink like `is_executable` does.
+    pub(crate) fn is_executable_no_follow(path: &Path) -> bool {
+        path.is_executable()
+    }
+
+    impl IsExecutableByCurrentUser for Path {
+        fn is_executable_by_current_user(&self) -> bool {
+            // Windows doesn't have a unix-style owner/group/other
+            // permission model to weigh against the current user, so this
+            // just falls back to `is_executable`.
+            self.is_executable()
+        }
+    }
+}
+
+#[cfg(target_os = "wasi")]
+mod wasi {
+    use std::os::wasi::fs::MetadataExt;
+    use Path;
+
+    use super::IsExecutable;
+    use super::IsExecutableByCurrentUser;
+
+    impl IsExecutable for Path {
+        fn is_executable(&self) -> bool {
+            let metadata = match self.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => return false,
+            };
+            metadata.is_file() && metadata.mode() & 0o111 != 0
+        }
+    }
+
+    /// Like `IsExecutable::is_executable`, but without following a symlink:
+    /// a symlink is not a regular file, so it is always reported as not
+    /// executable.
+    pub(crate) fn is_executable_no_follow(path: &Path) -> bool {
+        let metadata = match path.symlink_metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => return false,
+        };
+        metadata.is_file() && metadata.mode() & 0o111 != 0
+    }
+
+    impl IsExecutableByCurrentUser for Path {
+        fn is_executable_by_current_user(&self) -> bool {
+            // WASI has no process uid/gid/supplementary-groups concept to
+            // weigh against the file's owner, so this just falls back to
+            // `is_executable`.
+            self.is_executable()
+        }
+    }
+}
+
+#[cfg(all(target_family = "wasm", not(target_os = "wasi")))]
+mod wasm {
+    use Path;
+
+    use super::IsExecutable;
+    use super::IsExecutableByCurrentUser;
+
+    impl IsExecutable for Path {
+        fn is_executable(&self) -> bool {
+            // Bare `wasm32` (without WASI) has no filesystem to query, so
+            // there's no way to answer this; always say no.
+            false
+        }
+    }
+
+    /// Bare `wasm32` has no filesystem to query, so there's nothing to
+    /// distinguish; always say no, just like `is_executable`.
+    pub(crate) fn is_executable_no_follow(_path: &Path) -> bool {
+        false
+    }
+
+    impl IsExecutableByCurrentUser for Path {
+        fn is_executable_by_current_user(&self) -> bool {
+            // No filesystem, no process credentials; always say no, just
+            // like `is_executable`.
+            self.is_executable()
+        }
+    }
 }
@@ -26,6 +26,114 @@ mod unix {
     fn not_executable_directory() {
         assert!(!is_executable("."));
     }
+
+    #[test]
+    fn find_in_path() {
+        use is_executable::{find_all_in_path, find_in_path};
+
+        let dir = std::path::Path::new("./tests").canonicalize().unwrap();
+        let old_path = std::env::var_os("PATH");
+        std::env::set_var("PATH", &dir);
+
+        assert_eq!(
+            find_in_path("i_am_executable"),
+            Some(dir.join("i_am_executable"))
+        );
+        assert_eq!(find_in_path("i_am_not_executable"), None);
+        assert_eq!(
+            find_all_in_path("i_am_executable"),
+            vec![dir.join("i_am_executable")]
+        );
+
+        match old_path {
+            Some(old_path) => std::env::set_var("PATH", old_path),
+            None => std::env::remove_var("PATH"),
+        }
+    }
+
+    #[test]
+    fn executables_in_dir() {
+        use is_executable::executables_in_dir;
+
+        let found = executables_in_dir("./tests")
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert!(found.contains(&std::path::PathBuf::from("./tests/i_am_executable")));
+        assert!(found.contains(&std::path::PathBuf::from(
+            "./tests/i_am_executable_and_symlink"
+        )));
+        assert!(!found.contains(&std::path::PathBuf::from(
+            "./tests/i_am_not_executable"
+        )));
+    }
+
+    #[test]
+    fn path_executables_dedupes_by_file_name() {
+        use is_executable::path_executables;
+
+        let dir = std::path::Path::new("./tests").canonicalize().unwrap();
+        let old_path = std::env::var_os("PATH");
+        std::env::set_var(
+            "PATH",
+            std::env::join_paths(vec![&dir, &dir]).unwrap(),
+        );
+
+        let names = path_executables()
+            .filter_map(|path| path.file_name().map(|name| name.to_os_string()))
+            .collect::<Vec<_>>();
+        let unique = names
+            .iter()
+            .cloned()
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(names.len(), unique.len());
+        assert!(!names.is_empty());
+
+        match old_path {
+            Some(old_path) => std::env::set_var("PATH", old_path),
+            None => std::env::remove_var("PATH"),
+        }
+    }
+
+    #[test]
+    fn is_executable_by_current_user() {
+        use is_executable::is_executable_by_current_user;
+
+        assert!(is_executable_by_current_user("./tests/i_am_executable"));
+        assert!(!is_executable_by_current_user(
+            "./tests/i_am_not_executable"
+        ));
+        // A directory always carries a search/exec bit, but it isn't a
+        // regular file, so it must not be reported as executable.
+        assert!(!is_executable_by_current_user("."));
+        assert!(!is_executable_by_current_user(
+            "./tests/this-file-does-not-exist"
+        ));
+    }
+
+    #[test]
+    fn executable_check_builder() {
+        use is_executable::ExecutableCheck;
+
+        // Default behavior matches `is_executable`: follow symlinks, don't
+        // require current-user access.
+        assert!(ExecutableCheck::new().is_executable("./tests/i_am_executable"));
+        assert!(ExecutableCheck::new().is_executable("./tests/i_am_executable_and_symlink"));
+        assert!(!ExecutableCheck::new().is_executable("./tests/i_am_not_executable"));
+
+        assert!(ExecutableCheck::new()
+            .require_current_user(true)
+            .is_executable("./tests/i_am_executable"));
+        assert!(!ExecutableCheck::new()
+            .require_current_user(true)
+            .is_executable("./tests/i_am_not_executable"));
+
+        // `follow_symlinks(false)` should still report `false` for a path
+        // that doesn't exist at all, symlink or otherwise.
+        assert!(!ExecutableCheck::new()
+            .follow_symlinks(false)
+            .is_executable("./tests/this-file-does-not-exist"));
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -48,7 +156,32 @@ mod windows {
     }
 }
 
-#[cfg(any(target_os = "wasi", target_family = "wasm"))]
+#[cfg(target_os = "wasi")]
+mod wasi {
+    use super::*;
+
+    #[test]
+    fn executable() {
+        assert!(is_executable("./tests/i_am_executable"));
+    }
+
+    #[test]
+    fn executable_symlink() {
+        assert!(is_executable("./tests/i_am_executable_and_symlink"));
+    }
+
+    #[test]
+    fn not_executable_symlink() {
+        assert!(!is_executable("./tests/i_am_not_executable_and_symlink"));
+    }
+
+    #[test]
+    fn not_executable_directory() {
+        assert!(!is_executable("."));
+    }
+}
+
+#[cfg(all(target_family = "wasm", not(target_os = "wasi")))]
 mod wasm {
     use super::*;
 